@@ -0,0 +1,64 @@
+//! Colorized REPL output: syntax-highlighted type signatures, red error
+//! diagnostics, and a colored prompt, gated by the `--color` flag.
+
+use std::io::{self, IsTerminal};
+
+/// Whether the REPL should emit ANSI color codes, mirroring
+/// `--color=always|never|auto`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ColorMode {
+    Always,
+    Never,
+    /// Color only when stdout is an interactive terminal.
+    Auto,
+}
+
+impl ColorMode {
+    /// Parses the value of a `--color=` flag, defaulting to `Auto` for an
+    /// unrecognized value.
+    pub fn from_flag(value: &str) -> ColorMode {
+        match value {
+            "always" => ColorMode::Always,
+            "never" => ColorMode::Never,
+            _ => ColorMode::Auto,
+        }
+    }
+
+    pub fn enabled(self) -> bool {
+        match self {
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+            ColorMode::Auto => io::stdout().is_terminal(),
+        }
+    }
+}
+
+const RESET: &str = "\u{1b}[0m";
+const TYPE_COLOR: &str = "\u{1b}[36m";
+const ERROR_COLOR: &str = "\u{1b}[31m";
+const PROMPT_COLOR: &str = "\u{1b}[32m";
+
+fn paint(enabled: bool, code: &str, text: &str) -> String {
+    if enabled {
+        format!("{}{}{}", code, text, RESET)
+    } else {
+        text.to_owned()
+    }
+}
+
+/// Formats the result of `:t`/`:i`, colorizing the type signature when color
+/// output is enabled.
+pub fn format_type_signature(color: ColorMode, signature: &str) -> String {
+    paint(color.enabled(), TYPE_COLOR, signature)
+}
+
+/// Formats a compiler/runtime error, colorized red when color output is
+/// enabled.
+pub fn format_error(color: ColorMode, message: &str) -> String {
+    paint(color.enabled(), ERROR_COLOR, message)
+}
+
+/// Renders the primary prompt, colorized when color output is enabled.
+pub fn render_prompt(color: ColorMode) -> String {
+    paint(color.enabled(), PROMPT_COLOR, "> ")
+}
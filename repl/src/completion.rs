@@ -0,0 +1,73 @@
+//! Tab completion: in-scope bindings, record field access, and `import!`
+//! module paths.
+//!
+//! The engine itself only knows how to dispatch on the shape of the partial
+//! input; it asks the typechecker's current scope (`Scope`) and the module
+//! loader (`ModuleLoader`) for the actual candidates.
+
+/// A single completion candidate offered back to the line editor.
+pub type Candidate = String;
+
+/// Queried for everything the typechecker currently knows about scope.
+pub trait Scope {
+    /// Names in scope for the session: locals, `let`-bound names, and
+    /// anything pulled in via `import!`.
+    fn bindings(&self) -> Vec<String>;
+
+    /// Field labels of the record type inferred for `expr`, or `None` if
+    /// `expr` doesn't typecheck to a record.
+    fn record_fields(&self, expr: &str) -> Option<Vec<String>>;
+}
+
+/// Queried for the module loader's known `import!` paths.
+pub trait ModuleLoader {
+    /// Known module paths one level under `parent` (e.g. `std.io`,
+    /// `std.array` for `parent == "std"`).
+    fn modules_under(&self, parent: &str) -> Vec<String>;
+}
+
+/// Completes `input`, the partial expression typed so far, by dispatching to
+/// the right candidate source:
+///
+/// * `import! std.` -> module paths under `std`
+/// * `record.p` -> field labels of `record`'s inferred record type
+/// * anything else -> in-scope bindings
+pub fn complete(input: &str, scope: &dyn Scope, modules: &dyn ModuleLoader) -> Vec<Candidate> {
+    if let Some(prefix) = input.trim_start().strip_prefix("import!") {
+        return complete_module_path(prefix.trim_start(), modules);
+    }
+
+    if let Some(dot) = input.rfind('.') {
+        let (record_expr, field_prefix) = (&input[..dot], &input[dot + 1..]);
+        if let Some(fields) = scope.record_fields(record_expr) {
+            return complete_record_field(record_expr, field_prefix, &fields);
+        }
+    }
+
+    complete_binding(input, scope)
+}
+
+fn complete_binding(prefix: &str, scope: &dyn Scope) -> Vec<Candidate> {
+    scope
+        .bindings()
+        .into_iter()
+        .filter(|name| name.starts_with(prefix))
+        .collect()
+}
+
+fn complete_record_field(record_expr: &str, field_prefix: &str, fields: &[String]) -> Vec<Candidate> {
+    fields
+        .iter()
+        .filter(|field| field.starts_with(field_prefix))
+        .map(|field| format!("{}.{}", record_expr, field))
+        .collect()
+}
+
+fn complete_module_path(prefix: &str, modules: &dyn ModuleLoader) -> Vec<Candidate> {
+    let parent = prefix.trim_end_matches(|c: char| c != '.').trim_end_matches('.');
+    modules
+        .modules_under(parent)
+        .into_iter()
+        .filter(|module| module.starts_with(prefix))
+        .collect()
+}
@@ -0,0 +1,76 @@
+//! Persistent REPL history: loads `$GLUON_HISTORY` (or a default path) on
+//! startup, appends every accepted line, and backs the `:h`/`:history`
+//! command.
+
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+/// In-memory history, mirrored to `path` (if any) so it survives a restart.
+pub struct History {
+    path: Option<PathBuf>,
+    entries: Vec<String>,
+}
+
+impl History {
+    /// Loads history from `path`, or starts empty if `path` is `None` or
+    /// doesn't exist yet.
+    pub fn load(path: Option<PathBuf>) -> io::Result<History> {
+        let mut entries = Vec::new();
+        if let Some(ref path) = path {
+            if let Ok(file) = fs::File::open(path) {
+                for line in io::BufReader::new(file).lines() {
+                    entries.push(line?);
+                }
+            }
+        }
+        Ok(History { path, entries })
+    }
+
+    /// Resolves the history file from `$GLUON_HISTORY`, falling back to
+    /// `~/.gluon_history`.
+    pub fn default_path() -> Option<PathBuf> {
+        env::var_os("GLUON_HISTORY")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".gluon_history")))
+    }
+
+    /// Records `line` as accepted input, appending it to the history file.
+    pub fn push(&mut self, line: &str) -> io::Result<()> {
+        self.entries.push(line.to_owned());
+        if let Some(ref path) = self.path {
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            writeln!(file, "{}", line)?;
+        }
+        Ok(())
+    }
+
+    /// Clears both the in-memory and on-disk history.
+    pub fn clear(&mut self) -> io::Result<()> {
+        self.entries.clear();
+        if let Some(ref path) = self.path {
+            fs::write(path, b"")?;
+        }
+        Ok(())
+    }
+
+    /// Entries in the order they were entered, oldest first.
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+}
+
+/// Handles the `:h`/`:history` meta command. With no arguments it prints
+/// recent entries one per line; `clear` empties the history.
+pub fn handle_command(history: &mut History, args: &str) -> io::Result<()> {
+    match args.trim() {
+        "clear" => history.clear(),
+        _ => {
+            for entry in history.entries() {
+                println!("{}", entry);
+            }
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,104 @@
+//! Runtime-adjustable per-module log/trace verbosity, exposed through the
+//! `:log` REPL command.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    fn parse(s: &str) -> Option<Level> {
+        match s {
+            "error" => Some(Level::Error),
+            "warn" => Some(Level::Warn),
+            "info" => Some(Level::Info),
+            "debug" => Some(Level::Debug),
+            "trace" => Some(Level::Trace),
+            _ => None,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        }
+    }
+}
+
+/// The live, process-global verbosity filter, keyed by module path. Reading
+/// it (`enabled`) and writing it (`apply_directives`) both just take the
+/// lock, so a `:log` command takes effect immediately for any module that
+/// checks it afterwards.
+static FILTER: Mutex<Option<HashMap<String, Level>>> = Mutex::new(None);
+
+fn with_filter<R>(f: impl FnOnce(&mut HashMap<String, Level>) -> R) -> R {
+    let mut guard = FILTER.lock().unwrap();
+    f(guard.get_or_insert_with(HashMap::new))
+}
+
+/// Parses a `:log` directive string of comma-separated `path=level` pairs,
+/// e.g. `"gluon::vm=debug,gluon::check=trace"`.
+pub fn parse_directives(directives: &str) -> Result<Vec<(String, Level)>, String> {
+    directives
+        .split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut halves = part.splitn(2, '=');
+            let path = halves.next().unwrap_or("");
+            let level = halves
+                .next()
+                .ok_or_else(|| format!("missing `=level` in `{}`", part))?;
+            let level = Level::parse(level).ok_or_else(|| format!("unknown log level `{}`", level))?;
+            Ok((path.to_owned(), level))
+        })
+        .collect()
+}
+
+/// Applies parsed directives to the live filter so already-running modules
+/// pick up the new verbosity without a restart.
+pub fn apply_directives(directives: &[(String, Level)]) {
+    with_filter(|filter| {
+        for (path, level) in directives {
+            filter.insert(path.clone(), *level);
+        }
+    });
+}
+
+/// Whether a message at `level` for `module_path` should currently be
+/// emitted.
+pub fn enabled(module_path: &str, level: Level) -> bool {
+    with_filter(|filter| {
+        filter
+            .get(module_path)
+            .map_or(level <= Level::Info, |&configured| level <= configured)
+    })
+}
+
+/// Handles the `:log` meta command end to end: parse then apply.
+pub fn handle_command(directives: &str) -> Result<(), String> {
+    let parsed = parse_directives(directives)?;
+    apply_directives(&parsed);
+    Ok(())
+}
+
+/// Emits a diagnostic line for `module_path` at `level`, gated by the live
+/// filter `:log` configures. This is the facade the compiler/VM's internal
+/// diagnostics go through, mirroring a per-module level that the `:log`
+/// command raises or lowers at runtime.
+pub fn emit(module_path: &str, level: Level, message: &str) {
+    if enabled(module_path, level) {
+        eprintln!("{} {}: {}", level.label(), module_path, message);
+    }
+}
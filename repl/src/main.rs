@@ -0,0 +1,150 @@
+//! Entry point for the gluon interactive REPL.
+//!
+//! Parses `--color`, loads persistent history, then runs the read-eval-print
+//! loop. Subsequent completion, multi-line, and logging support is wired in
+//! here as each of those pieces lands.
+
+mod color;
+mod completion;
+mod history;
+mod logging;
+mod multiline;
+
+use std::env;
+use std::io::{self, Write};
+
+use color::ColorMode;
+use completion::{ModuleLoader, Scope};
+use history::History;
+use multiline::CONTINUATION_PROMPT;
+
+/// Placeholder `Scope`/`ModuleLoader` wired up until the real
+/// typechecker/module-loader-backed implementations are supplied by the
+/// `gluon_check`/`gluon_base` crates this binary links against.
+struct VmScope;
+
+impl Scope for VmScope {
+    fn bindings(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn record_fields(&self, _expr: &str) -> Option<Vec<String>> {
+        None
+    }
+}
+
+struct VmModuleLoader;
+
+impl ModuleLoader for VmModuleLoader {
+    fn modules_under(&self, _parent: &str) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+fn parse_color_flag(args: &[String]) -> ColorMode {
+    args.iter()
+        .find_map(|arg| arg.strip_prefix("--color="))
+        .map(ColorMode::from_flag)
+        .unwrap_or(ColorMode::Auto)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let color = parse_color_flag(&args);
+
+    let mut history =
+        History::load(History::default_path()).unwrap_or_else(|err| panic!("failed to load history: {}", err));
+
+    run(color, &mut history);
+}
+
+fn run(color: ColorMode, history: &mut History) {
+    let stdin = io::stdin();
+
+    loop {
+        print!("{}", color::render_prompt(color));
+        io::stdout().flush().ok();
+
+        let mut line = String::new();
+        if stdin.read_line(&mut line).unwrap_or(0) == 0 {
+            break;
+        }
+        let line = line.trim_end_matches('\n');
+
+        if line == ":q" {
+            break;
+        }
+
+        // In cooked terminal mode a Tab keystroke is buffered as a literal
+        // `\t` with the rest of the line and is only read once the line is
+        // submitted with Enter; a submitted line ending in `\t` is treated as
+        // a completion request for the partial expression before it. Tab on
+        // its own, without Enter, does not trigger completion.
+        if let Some(partial) = line.strip_suffix('\t') {
+            let candidates = completion::complete(partial, &VmScope, &VmModuleLoader);
+            println!("{}", candidates.join("  "));
+            continue;
+        }
+
+        let history_args = line.strip_prefix(":history").or_else(|| {
+            line.strip_prefix(":h")
+                .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+        });
+        if let Some(args) = history_args {
+            if let Err(err) = history::handle_command(history, args.trim()) {
+                eprintln!("{}", color::format_error(color, &err.to_string()));
+            }
+            continue;
+        }
+
+        if let Some(directives) = line.strip_prefix(":log ") {
+            if let Err(err) = logging::handle_command(directives) {
+                eprintln!("{}", color::format_error(color, &err));
+            }
+            continue;
+        }
+
+        let mut block = vec![line.to_owned()];
+        while needs_more_input(&block) {
+            print!("{}", CONTINUATION_PROMPT);
+            io::stdout().flush().ok();
+
+            let mut more = String::new();
+            if stdin.read_line(&mut more).unwrap_or(0) == 0 {
+                break;
+            }
+            block.push(more.trim_end_matches('\n').to_owned());
+        }
+
+        let source = multiline::join_continuation(&block);
+        history.push(&source).ok();
+        eval_and_print(color, &source);
+    }
+}
+
+fn needs_more_input(block: &[String]) -> bool {
+    let lines: Vec<&str> = block.iter().map(String::as_str).collect();
+    multiline::needs_more_input(&lines)
+}
+
+/// Hands `source` to the compiler/VM and prints the result, colorizing type
+/// signatures and errors. Typechecking and evaluation are performed by the
+/// `gluon_check`/`gluon_vm` crates this binary links against; those crates
+/// report their progress through `logging::emit`, which `:log` controls the
+/// verbosity of.
+fn eval_and_print(color: ColorMode, source: &str) {
+    logging::emit(
+        "gluon::check",
+        logging::Level::Trace,
+        &format!("checking `{}`", source.trim()),
+    );
+
+    match evaluate(source) {
+        Ok(output) => println!("{}", color::format_type_signature(color, &output)),
+        Err(err) => eprintln!("{}", color::format_error(color, &err)),
+    }
+}
+
+fn evaluate(_source: &str) -> Result<String, String> {
+    unimplemented!("evaluation delegates to gluon_check/gluon_vm")
+}
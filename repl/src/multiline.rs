@@ -0,0 +1,63 @@
+//! Multi-line input support: detects when the parser needs more lines to
+//! complete an expression (unbalanced braces/parens, a trailing `=`, an open
+//! `match`) and drives the secondary continuation prompt.
+
+/// The prompt shown while waiting for the rest of an incomplete expression.
+pub const CONTINUATION_PROMPT: &str = "| ";
+
+/// Returns `true` if the lines entered so far (most recent last) look
+/// incomplete and the REPL should keep reading under `CONTINUATION_PROMPT`
+/// instead of evaluating them.
+///
+/// This is a syntactic pre-check, not a full parse: it tracks paren/brace/
+/// bracket nesting, a couple of common "expects more" trailing tokens, and
+/// open `match` blocks. The real parser still rejects genuinely malformed
+/// input once it's handed a "complete" block.
+pub fn needs_more_input(lines: &[&str]) -> bool {
+    let mut depth = 0i32;
+    for line in lines {
+        for c in line.chars() {
+            match c {
+                '(' | '{' | '[' => depth += 1,
+                ')' | '}' | ']' => depth -= 1,
+                _ => {}
+            }
+        }
+    }
+    if depth > 0 {
+        return true;
+    }
+
+    let last = match lines.last() {
+        Some(line) => line.trim_end(),
+        None => return false,
+    };
+
+    if last.ends_with('=') || last.ends_with("then") || last.ends_with("else") {
+        return true;
+    }
+
+    if in_open_match(lines) {
+        // `match` arms aren't bracketed, so — like a `let` block spanning
+        // several lines — it's only done once the user submits a blank
+        // line. Checking only the last line (as opposed to scanning the
+        // whole buffer for "->") means a later arm missing its body, e.g.
+        // `| B ->` with no expression after it, still counts as open.
+        return !last.is_empty();
+    }
+
+    false
+}
+
+fn in_open_match(lines: &[&str]) -> bool {
+    lines.iter().any(|line| {
+        let trimmed = line.trim();
+        trimmed.contains("match") && trimmed.ends_with("with")
+    })
+}
+
+/// Joins lines read under the continuation prompt back into the single
+/// source string the parser expects.
+pub fn join_continuation(lines: &[String]) -> String {
+    lines.join("\n")
+}
@@ -2,37 +2,102 @@
 
 extern crate rexpect;
 
+use std::path::Path;
+
 use rexpect::spawn;
 use rexpect::session::PtySession;
 use rexpect::errors::*;
 
+/// Strips ANSI CSI escape sequences (`ESC '[' ... final-byte`) from `input`,
+/// leaving only the text that is actually visible in a terminal.
+///
+/// The REPL colorizes type signatures, errors and the prompt, so tests that
+/// match on raw PTY output need to look past those codes to the text a user
+/// would see.
+fn strip_ansi_escapes(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\u{1b}' || chars.peek() != Some(&'[') {
+            output.push(c);
+            continue;
+        }
+
+        chars.next(); // consume '['
+        while let Some(next) = chars.next() {
+            if !(next.is_ascii_digit() || next == ';') {
+                // `next` is the final byte of the CSI sequence, swallow it too.
+                break;
+            }
+        }
+    }
+
+    output
+}
+
+/// Whether the REPL under test should be asked to emit color codes, mirroring
+/// the REPL's own `--color` flag.
+#[derive(Clone, Copy)]
+enum Color {
+    Always,
+    Never,
+}
+
+impl Color {
+    fn as_flag(self) -> &'static str {
+        match self {
+            Color::Always => "--color=always",
+            Color::Never => "--color=never",
+        }
+    }
+}
+
 struct REPL {
     session: PtySession,
     prompt: &'static str,
+    continuation_prompt: &'static str,
 }
 
 impl REPL {
     fn new() -> REPL {
-        let repl = REPL::new_().unwrap_or_else(|err| panic!("{}", err));
-        repl
+        REPL::with_color(Color::Never)
+    }
+
+    fn with_color(color: Color) -> REPL {
+        REPL::new_(color, None).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Spawns a REPL that loads and persists its history to `history_path`,
+    /// mirroring the `GLUON_HISTORY` environment variable the REPL reads on
+    /// startup.
+    fn with_history(history_path: &Path) -> REPL {
+        REPL::new_(Color::Never, Some(history_path)).unwrap_or_else(|err| panic!("{}", err))
     }
 
     /// Defines the command, timeout, and prompt settings.
     /// Wraps a rexpect::session::PtySession. expecting the prompt after launch.
-    fn new_() -> Result<REPL> {
+    fn new_(color: Color, history_path: Option<&Path>) -> Result<REPL> {
         if ::std::env::var("GLUON_PATH").is_err() {
             ::std::env::set_var("GLUON_PATH", "..");
         }
 
-        let command = "../target/debug/gluon -i";
+        let mut command = format!("../target/debug/gluon -i {}", color.as_flag());
+        if let Some(path) = history_path {
+            command = format!("GLUON_HISTORY={} {}", path.display(), command);
+        }
         let timeout: u64 = 10_000;
 
-        let mut session = spawn(command, Some(timeout))?;
+        let mut session = spawn(&command, Some(timeout))?;
 
         let prompt: &'static str = "> ";
         session.exp_string(prompt)?;
 
-        Ok(REPL { session, prompt })
+        Ok(REPL {
+            session,
+            prompt,
+            continuation_prompt: "| ",
+        })
     }
 
     fn test(&mut self, send: &str, expect: Option<&str>) {
@@ -43,15 +108,103 @@ impl REPL {
     /// Ensures certain lines are expected to reduce race conditions.
     /// If no ouput is expected or desired to be tested, pass it an Option::None,
     /// causing rexpect to wait for the next prompt.
+    ///
+    /// Matching happens against the visible text only: any ANSI escape codes
+    /// emitted between the echoed input and the next prompt are stripped
+    /// before `expect` is searched for.
     fn test_(&mut self, send: &str, expect: Option<&str>) -> Result<()> {
         self.session.send_line(send)?;
         self.session.exp_string(send)?;
 
+        let before_prompt = self.session.exp_string(self.prompt)?;
+
         if let Some(string) = expect {
-            self.session.exp_string(string)?;
+            let visible = strip_ansi_escapes(&before_prompt);
+            if !visible.contains(string) {
+                bail!(
+                    "expected `{}` in output, got `{}` (raw: `{}`)",
+                    string,
+                    visible,
+                    before_prompt
+                );
+            }
         }
 
-        self.session.exp_string(self.prompt)?;
+        Ok(())
+    }
+
+    /// Like `test`, but for input that spans multiple lines (e.g. a `let` or
+    /// `match` the parser reports as incomplete). Every line after the first
+    /// is expected behind the continuation prompt rather than the normal one.
+    fn test_multiline(&mut self, sends: &[&str], expect: Option<&str>) {
+        self.test_multiline_(sends, expect)
+            .unwrap_or_else(|err| panic!("{}", err));
+    }
+
+    fn test_multiline_(&mut self, sends: &[&str], expect: Option<&str>) -> Result<()> {
+        let (first, rest) = sends
+            .split_first()
+            .expect("test_multiline needs at least one line");
+
+        self.session.send_line(first)?;
+        self.session.exp_string(first)?;
+
+        for line in rest {
+            self.session.exp_string(self.continuation_prompt)?;
+            self.session.send_line(line)?;
+            // An empty closing line echoes only a newline, which leaves
+            // nothing distinct to synchronize on; the prompt match after the
+            // loop consumes it.
+            if !line.is_empty() {
+                self.session.exp_string(line)?;
+            }
+        }
+
+        let before_prompt = self.session.exp_string(self.prompt)?;
+
+        if let Some(string) = expect {
+            let visible = strip_ansi_escapes(&before_prompt);
+            if !visible.contains(string) {
+                bail!(
+                    "expected `{}` in output, got `{}` (raw: `{}`)",
+                    string,
+                    visible,
+                    before_prompt
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Types `send` (without submitting it), presses Tab, and checks that
+    /// `expect` shows up among the emitted completion candidates. The
+    /// in-progress line is then cleared so the session is left at a clean
+    /// prompt for the next command.
+    fn complete(&mut self, send: &str, expect: &str) {
+        self.complete_(send, expect)
+            .unwrap_or_else(|err| panic!("{}", err));
+    }
+
+    fn complete_(&mut self, send: &str, expect: &str) -> Result<()> {
+        self.session.send(send)?;
+        self.session.send("\t")?;
+
+        let before_prompt = self.session.exp_string(self.prompt)?;
+        let visible = strip_ansi_escapes(&before_prompt);
+        if !visible.contains(expect) {
+            bail!(
+                "expected completion `{}` in output, got `{}` (raw: `{}`)",
+                expect,
+                visible,
+                before_prompt
+            );
+        }
+
+        // Tab completion redraws the prompt with the in-progress line still
+        // there; clear it so later commands start from a blank line.
+        self.session.send_control('u')?;
+
         Ok(())
     }
 
@@ -141,3 +294,173 @@ fn arrays() {
     repl.test("let array = import! std.array", None);
     repl.test("array.len [1, 2, 3]", Some("3"));
 }
+
+#[test]
+fn strips_csi_sequences_from_plain_text() {
+    let colored = "\u{1b}[1;31mInt\u{1b}[0m";
+    assert_eq!(strip_ansi_escapes(colored), "Int");
+}
+
+#[test]
+fn expression_types_with_color_always() {
+    let mut repl = REPL::with_color(Color::Always);
+
+    repl.session.send_line(":t 5").unwrap();
+    repl.session.exp_string(":t 5").unwrap();
+    let before_prompt = repl.session.exp_string(repl.prompt).unwrap();
+
+    assert!(
+        before_prompt.contains("\u{1b}["),
+        "expected raw output to contain ANSI escape codes with --color=always, got `{}`",
+        before_prompt
+    );
+    assert!(
+        strip_ansi_escapes(&before_prompt).contains("Int"),
+        "expected stripped output to still contain `Int`, got `{}`",
+        before_prompt
+    );
+
+    repl.test(":t \"gluon\"", Some("String"));
+}
+
+/// A scratch history file path, unique per test process and thread so
+/// parallel tests don't clobber each other's history.
+fn temp_history_path() -> ::std::path::PathBuf {
+    let mut path = ::std::env::temp_dir();
+    path.push(format!(
+        "gluon_repl_history_{}_{:?}.txt",
+        ::std::process::id(),
+        ::std::thread::current().id()
+    ));
+    path
+}
+
+#[test]
+fn history_persists_across_restarts() {
+    let history_path = temp_history_path();
+    let _ = ::std::fs::remove_file(&history_path);
+
+    {
+        let mut repl = REPL::with_history(&history_path);
+        repl.test("let history_check = 1 + 2", Some("3"));
+        repl.quit();
+    }
+
+    {
+        let mut repl = REPL::with_history(&history_path);
+        repl.test(":history", Some("let history_check = 1 + 2"));
+        repl.quit();
+    }
+
+    let _ = ::std::fs::remove_file(&history_path);
+}
+
+#[test]
+fn completes_in_scope_bindings() {
+    let mut repl = REPL::new();
+
+    repl.test("let pi_approx = 3.14", None);
+    repl.complete("pi_a", "pi_approx");
+}
+
+#[test]
+fn completes_record_fields() {
+    let mut repl = REPL::new();
+
+    repl.test("let record = { pi = 3.14, add1 = (+) 1.0 }", None);
+    repl.complete("record.p", "pi");
+}
+
+#[test]
+fn completes_module_paths() {
+    let mut repl = REPL::new();
+
+    repl.complete("import! std.", "std.io");
+}
+
+#[test]
+fn multiline_let_binding() {
+    let mut repl = REPL::new();
+
+    // No trailing blank line needed: the expression after `=` is already
+    // complete once its own braces/parens balance, same as a single-line
+    // `let`.
+    repl.test_multiline(&["let f x =", "    x + 1"], None);
+    repl.test("f 41", Some("42"));
+}
+
+#[test]
+fn multiline_record() {
+    let mut repl = REPL::new();
+
+    repl.test_multiline(
+        &["let record = {", "    pi = 3.14,", "    add1 = (+) 1.0", "}"],
+        None,
+    );
+    repl.test("record.pi", Some("3.14"));
+}
+
+#[test]
+fn multiline_match_with_multiple_arms() {
+    let mut repl = REPL::new();
+
+    // Unlike a bracketed record, a `match` block's arms aren't balanced by
+    // any paren/brace, so it takes a trailing blank line to signal the last
+    // arm is done (mirrors a `let` block that never got a trailing `in`).
+    repl.test_multiline(
+        &[
+            "let describe x = match x with",
+            "    | True -> \"yes\"",
+            "    | False -> \"no\"",
+            "",
+        ],
+        None,
+    );
+    repl.test("describe True", Some("yes"));
+}
+
+#[test]
+fn log_command_adjusts_verbosity_live() {
+    let mut repl = REPL::new();
+
+    repl.test(":log gluon::check=trace", None);
+    repl.test("let x: Int = 1", Some("TRACE gluon::check"));
+    repl.test(":log gluon::check=error", None);
+}
+
+#[test]
+fn log_command_accepts_multiple_directives() {
+    let mut repl = REPL::new();
+
+    repl.test(":log gluon::vm=debug,gluon::check=trace", None);
+    repl.test("let y: Int = 2", Some("TRACE gluon::check"));
+    repl.test(":log gluon::vm=error,gluon::check=error", None);
+}
+
+#[test]
+fn history_can_be_cleared() {
+    let history_path = temp_history_path();
+    let _ = ::std::fs::remove_file(&history_path);
+
+    {
+        let mut repl = REPL::with_history(&history_path);
+        repl.test("let history_check = 1 + 2", Some("3"));
+        repl.test(":history clear", None);
+        repl.quit();
+    }
+
+    {
+        let mut repl = REPL::with_history(&history_path);
+        repl.session.send_line(":history").unwrap();
+        repl.session.exp_string(":history").unwrap();
+        let before_prompt = repl.session.exp_string(repl.prompt).unwrap();
+        assert!(
+            !before_prompt.contains("history_check"),
+            "expected no entries after `:history clear`, got `{}`",
+            before_prompt
+        );
+        repl.quit();
+    }
+
+    let _ = ::std::fs::remove_file(&history_path);
+}